@@ -7,12 +7,14 @@ use std::{
 use serde::Deserialize;
 use swc_core::{
     atoms::Atom,
-    common::{BytePos, FileName, SourceFile, Span, Spanned, DUMMY_SP},
+    common::{errors::HANDLER, BytePos, FileName, SourceFile, Span, Spanned, DUMMY_SP},
     ecma::{
         ast::{
-            BlockStmt, EsVersion, Expr, ExprOrSpread, ExprStmt, Function, Ident, ImportDecl,
-            ImportDefaultSpecifier, ImportPhase, ImportSpecifier, Lit, Module, ModuleDecl,
-            ModuleExportName, ModuleItem, NewExpr, Program, Stmt, ThrowStmt,
+            ArrowExpr, BlockStmt, BlockStmtOrExpr, Callee, ClassDecl, ClassMethod, Decl, EsVersion,
+            Expr, ExprOrSpread, ExprStmt, Function, Ident, ImportDecl, ImportDefaultSpecifier,
+            ImportNamedSpecifier, ImportPhase, ImportSpecifier, ImportStarAsSpecifier, Lit,
+            MethodProp, Module, ModuleDecl, ModuleExportName, ModuleItem, NewExpr, ObjectPatProp,
+            Pat, Program, PropName, Stmt, ThrowStmt, VarDeclarator,
         },
         parser::{parse_file_as_module, PResult, Syntax::Typescript, TsSyntax},
         visit::{as_folder, FoldWith, VisitMut, VisitMutWith},
@@ -22,10 +24,50 @@ use swc_core::{
 
 // cwd gets mapped to /cwd by the swc plugin runner.
 const PROMPTS_FILE: &str = "/cwd/node_modules/.swc-plugin-use-prompt/prompts";
+const DEFAULT_DIRECTIVE: &str = "use prompt:";
+
+/// User-supplied plugin configuration, passed in as the SWC plugin's JSON
+/// config and read via `TransformPluginProgramMetadata::get_transform_plugin_config`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PluginConfig {
+    /// Path to the sidecar substitutions file written by the Node side.
+    /// Defaults to [`PROMPTS_FILE`].
+    prompts_file: Option<String>,
+    /// Directive keyword that prefixes a prompt, e.g. `"use prompt:"`.
+    directive: Option<String>,
+    /// Skip inserting the automatic `"use client"` directive, for projects
+    /// that only use prompted components as server components.
+    #[serde(default)]
+    disable_use_client: bool,
+    /// Skip inserting the automatic `import React from "react"`, for
+    /// projects on the automatic JSX runtime that don't need `React` in scope.
+    #[serde(default)]
+    disable_react_import: bool,
+    /// When a prompted component hasn't been generated yet, inject a
+    /// runtime throw instead of just emitting a "waiting for generation"
+    /// build warning. Off by default.
+    #[serde(default)]
+    runtime_fallback: bool,
+}
+
+/// Emit a build-time error pointing at the offending function or directive.
+/// Used for malformed prompts and un-parseable codegen output, which can
+/// never resolve themselves at runtime.
+fn emit_prompt_diagnostic(span: Span, msg: &str) {
+    HANDLER.with(|handler| handler.struct_span_err(span, msg).emit());
+}
 
-/// Generate an error message to be thrown at runtime.
-/// TODO: Maybe there's a nice way to throw compile-time errors from SWC Plugins?
-fn make_prompt_error_body(msg: &str) -> Option<BlockStmt> {
+/// Emit a build-time warning pointing at the offending function or
+/// directive. Used for states that are expected to resolve on their own
+/// (e.g. generation still pending), so they shouldn't fail the build.
+fn emit_prompt_warning(span: Span, msg: &str) {
+    HANDLER.with(|handler| handler.struct_span_warn(span, msg).emit());
+}
+
+/// Generate an error message to be thrown at runtime, for cases that are
+/// expected to resolve on a future build (e.g. generation still pending).
+fn make_prompt_error_body(msg: &str) -> BlockStmt {
     let expr = ThrowStmt {
         arg: Box::new(
             NewExpr {
@@ -40,10 +82,10 @@ fn make_prompt_error_body(msg: &str) -> Option<BlockStmt> {
         ),
         ..Default::default()
     };
-    Some(BlockStmt {
+    BlockStmt {
         stmts: vec![expr.into()],
         ..Default::default()
-    })
+    }
 }
 
 fn make_module_from_source(source: &str) -> PResult<Module> {
@@ -148,11 +190,295 @@ impl VisitMut for RenameImportsVisitor {
     }
 }
 
+/// Rewrite CommonJS `const x = require("src")` / `const { a, b } =
+/// require("src")` declarations into prefixed ESM import declarations,
+/// capturing renamed locals in an IdentMap, mirroring `RenameImportsVisitor`
+/// for codegen output that uses `require` instead of `import`.
+struct RenameRequiresVisitor {
+    prefix: String,
+    pub ident_map: IdentMap,
+}
+impl RenameRequiresVisitor {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_owned(),
+            ident_map: HashMap::new(),
+        }
+    }
+
+    /// If `decl` is `<pat> = require("src")`, build the equivalent prefixed
+    /// `ImportDecl` and register its bindings in `ident_map`.
+    fn convert_require_decl(&mut self, decl: &VarDeclarator) -> Option<ImportDecl> {
+        let init = decl.init.as_ref()?;
+        let Expr::Call(call) = init.as_ref() else {
+            return None;
+        };
+        let Callee::Expr(callee) = &call.callee else {
+            return None;
+        };
+        let Expr::Ident(callee_ident) = callee.as_ref() else {
+            return None;
+        };
+        if callee_ident.sym != *"require" {
+            return None;
+        }
+        let [ExprOrSpread { spread: None, expr }] = call.args.as_slice() else {
+            return None;
+        };
+        let Expr::Lit(Lit::Str(src)) = expr.as_ref() else {
+            return None;
+        };
+
+        let specifiers = match &decl.name {
+            Pat::Ident(binding) => {
+                let key = binding.id.sym.clone();
+                let pfxed: Atom = format!("{}{key}", self.prefix).into();
+                self.ident_map.insert(key, pfxed.clone());
+                vec![ImportSpecifier::Default(ImportDefaultSpecifier {
+                    span: DUMMY_SP,
+                    local: Ident::from(pfxed),
+                })]
+            }
+            Pat::Object(obj) => {
+                let mut specifiers = Vec::with_capacity(obj.props.len());
+                let mut bindings = Vec::with_capacity(obj.props.len());
+                for prop in &obj.props {
+                    let (local, imported) = match prop {
+                        ObjectPatProp::Assign(assign) => (assign.key.id.sym.clone(), None),
+                        ObjectPatProp::KeyValue(kv) => {
+                            let Pat::Ident(binding) = kv.value.as_ref() else {
+                                // Nested/destructured bindings aren't representable as a
+                                // single import specifier - bail rather than drop them.
+                                return None;
+                            };
+                            let key = match &kv.key {
+                                PropName::Ident(ident) => ident.sym.clone(),
+                                PropName::Str(str) => str.value.clone(),
+                                _ => return None,
+                            };
+                            (binding.id.sym.clone(), Some(key))
+                        }
+                        // `...rest` and computed keys have no single static binding to
+                        // rewrite into an import specifier - bail on the whole decl
+                        // rather than silently drop the binding.
+                        ObjectPatProp::Rest(_) => return None,
+                    };
+                    let imported = imported.unwrap_or_else(|| local.clone());
+                    let pfxed: Atom = format!("{}{local}", self.prefix).into();
+                    bindings.push((local, pfxed.clone()));
+                    specifiers.push(ImportSpecifier::Named(ImportNamedSpecifier {
+                        span: DUMMY_SP,
+                        local: Ident::from(pfxed),
+                        imported: Some(ModuleExportName::Ident(imported.into())),
+                        is_type_only: false,
+                    }));
+                }
+                // Register renames only once every prop converted, so a bail
+                // above leaves the kept `require` bindings untouched.
+                self.ident_map.extend(bindings);
+                specifiers
+            }
+            _ => return None,
+        };
+
+        if specifiers.is_empty() {
+            return None;
+        }
+
+        Some(ImportDecl {
+            span: DUMMY_SP,
+            specifiers,
+            src: Box::new(src.clone()),
+            type_only: false,
+            with: None,
+            phase: ImportPhase::Evaluation,
+        })
+    }
+}
+impl VisitMut for RenameRequiresVisitor {
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        *items = items
+            .drain(..)
+            .map(|item| {
+                let ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) = &item else {
+                    return item;
+                };
+                let [decl] = var_decl.decls.as_slice() else {
+                    return item;
+                };
+                match self.convert_require_decl(decl) {
+                    Some(import_decl) => ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)),
+                    None => item,
+                }
+            })
+            .collect();
+    }
+}
+
 fn make_imports_from_source(source: &str, prefix: &str) -> PResult<(Vec<ModuleItem>, IdentMap)> {
     let mut ast = make_module_from_source(source)?;
     let mut vis = RenameImportsVisitor::new(prefix);
     vis.visit_mut_module(&mut ast);
-    Ok((ast.body, vis.ident_map.clone()))
+
+    let mut req_vis = RenameRequiresVisitor::new(prefix);
+    req_vis.visit_mut_module(&mut ast);
+
+    let mut ident_map = vis.ident_map.clone();
+    ident_map.extend(req_vis.ident_map);
+
+    Ok((ast.body, ident_map))
+}
+
+/// The name a named import binds, ignoring any local alias - used to detect
+/// that e.g. `{ useState as __a_useState }` and `{ useState as __b_useState }`
+/// import the same thing from the same module.
+fn named_import_key(spec: &ImportNamedSpecifier) -> Atom {
+    match &spec.imported {
+        Some(ModuleExportName::Ident(ident)) => ident.sym.clone(),
+        Some(ModuleExportName::Str(str)) => str.value.clone(),
+        None => spec.local.sym.clone(),
+    }
+}
+
+/// Coalesce hoisted `ImportDecl`s by source module, collapsing specifiers
+/// that import the same binding into a single shared local. Returns the
+/// merged import items plus a rename map (dropped local -> kept local) to
+/// apply over the rest of the module so substituted function bodies keep
+/// referring to a binding that still exists. Items that aren't imports (e.g.
+/// a `require` the CommonJS pass left as-is) are kept, after the imports.
+fn merge_imports(imports: Vec<ModuleItem>) -> (Vec<ModuleItem>, IdentMap) {
+    // Specifiers for a given src are bucketed by kind rather than appended to
+    // one shared `Vec`: a namespace specifier can't share a declaration with
+    // named specifiers (invalid ESM grammar), and a default specifier must
+    // come first whenever it's emitted alongside either. Keeping the kinds
+    // apart until the final assembly step lets us pick a valid shape instead
+    // of reproducing whatever order the visitor happened to encounter them in.
+    struct MergedImport {
+        base: ImportDecl,
+        default_spec: Option<ImportDefaultSpecifier>,
+        namespace_spec: Option<ImportStarAsSpecifier>,
+        named_specs: Vec<ImportNamedSpecifier>,
+    }
+
+    let mut by_src: Vec<(Atom, MergedImport)> = vec![];
+    let mut rename: IdentMap = HashMap::new();
+    let mut others: Vec<ModuleItem> = vec![];
+
+    for item in imports {
+        let mut decl = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(decl)) => decl,
+            other => {
+                others.push(other);
+                continue;
+            }
+        };
+        let src = decl.src.value.clone();
+        let specifiers = std::mem::take(&mut decl.specifiers);
+
+        let idx = match by_src.iter().position(|(s, _)| *s == src) {
+            Some(idx) => idx,
+            None => {
+                by_src.push((
+                    src,
+                    MergedImport {
+                        base: decl,
+                        default_spec: None,
+                        namespace_spec: None,
+                        named_specs: vec![],
+                    },
+                ));
+                by_src.len() - 1
+            }
+        };
+
+        let merged = &mut by_src[idx].1;
+        for spec in specifiers {
+            match spec {
+                ImportSpecifier::Named(named) => {
+                    let key = named_import_key(&named);
+                    let existing = merged
+                        .named_specs
+                        .iter()
+                        .find(|existing| named_import_key(existing) == key)
+                        .map(|existing| existing.local.sym.clone());
+                    match existing {
+                        Some(kept_local) => {
+                            rename.insert(named.local.sym.clone(), kept_local);
+                        }
+                        None => merged.named_specs.push(named),
+                    }
+                }
+                ImportSpecifier::Default(default_spec) => match &merged.default_spec {
+                    Some(existing) => {
+                        rename.insert(default_spec.local.sym.clone(), existing.local.sym.clone());
+                    }
+                    None => merged.default_spec = Some(default_spec),
+                },
+                ImportSpecifier::Namespace(ns_spec) => match &merged.namespace_spec {
+                    Some(existing) => {
+                        rename.insert(ns_spec.local.sym.clone(), existing.local.sym.clone());
+                    }
+                    None => merged.namespace_spec = Some(ns_spec),
+                },
+            }
+        }
+    }
+
+    let mut items: Vec<ModuleItem> = by_src
+        .into_iter()
+        .flat_map(|(_, merged)| {
+            let MergedImport {
+                base,
+                default_spec,
+                namespace_spec,
+                named_specs,
+            } = merged;
+
+            let mut decls = vec![];
+
+            if !named_specs.is_empty() {
+                // Default + named specifiers may share a declaration; the default
+                // (if any) is consumed here, so a sibling namespace decl below
+                // must not repeat it.
+                let mut specifiers = Vec::with_capacity(named_specs.len() + 1);
+                if let Some(default_spec) = default_spec {
+                    specifiers.push(ImportSpecifier::Default(default_spec));
+                }
+                specifiers.extend(named_specs.into_iter().map(ImportSpecifier::Named));
+                decls.push(ImportDecl {
+                    specifiers,
+                    ..base.clone()
+                });
+
+                if let Some(namespace_spec) = namespace_spec {
+                    decls.push(ImportDecl {
+                        specifiers: vec![ImportSpecifier::Namespace(namespace_spec)],
+                        ..base
+                    });
+                }
+            } else if let Some(namespace_spec) = namespace_spec {
+                // No named specifiers to clash with, so default + namespace can
+                // share one valid declaration.
+                let mut specifiers = vec![];
+                if let Some(default_spec) = default_spec {
+                    specifiers.push(ImportSpecifier::Default(default_spec));
+                }
+                specifiers.push(ImportSpecifier::Namespace(namespace_spec));
+                decls.push(ImportDecl { specifiers, ..base });
+            } else if let Some(default_spec) = default_spec {
+                decls.push(ImportDecl {
+                    specifiers: vec![ImportSpecifier::Default(default_spec)],
+                    ..base
+                });
+            }
+
+            decls
+                .into_iter()
+                .map(|decl| ModuleItem::ModuleDecl(ModuleDecl::Import(decl)))
+        })
+        .collect();
+    items.extend(others);
+    (items, rename)
 }
 
 #[derive(Deserialize, Debug)]
@@ -161,16 +487,52 @@ struct Substitution {
     imports: Option<String>,
 }
 
-type SubstitutionMap = HashMap<String, HashMap<String, HashMap<String, Substitution>>>;
+type SubstitutionMap = HashMap<String, Substitution>;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Compute a stable FNV-1a hash over the function's declared name and its
+/// trimmed prompt text. Used as the `SubstitutionMap` key so that edits
+/// elsewhere in the file (which shift every `BytePos` span below them) don't
+/// invalidate already-generated substitutions.
+fn hash_substitution_key(name: &str, prompt: &str) -> String {
+    let mut hash = FNV_OFFSET_BASIS;
+    for byte in name.bytes().chain(std::iter::once(0)).chain(prompt.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    format!("{hash:016x}")
+}
 
 pub struct SubstitutionVisitor {
     substitutions: SubstitutionMap,
     imports: Vec<ModuleItem>,
     visited: u32,
+    /// Tracks the name of the binding currently being visited (a `const Foo
+    /// = ...` declarator or a named class), so arrow functions and class
+    /// `render` methods - which have no name of their own - can still be
+    /// hashed by a stable name.
+    current_name: Option<String>,
+    /// When a prompted component hasn't been generated yet, opt-in fallback
+    /// that injects a runtime throw (matching the pre-diagnostics behavior)
+    /// instead of leaving the build to simply emit a waiting warning.
+    runtime_fallback: bool,
+    /// Directive keyword that prefixes a prompt, e.g. `"use prompt:"`.
+    directive: String,
+    /// Whether to insert the automatic `"use client"` directive once a
+    /// non-empty prompt has been found, whether or not its generated code
+    /// is available yet.
+    insert_use_client: bool,
 }
 
 impl SubstitutionVisitor {
-    pub fn new(cache_file: &str) -> Self {
+    pub fn new(
+        cache_file: &str,
+        directive: &str,
+        insert_use_client: bool,
+        runtime_fallback: bool,
+    ) -> Self {
         let contents = String::from_utf8(fs::read(cache_file).unwrap_or(b"{}".to_vec()))
             .expect("malformed substitutions json");
         let substitutions: SubstitutionMap =
@@ -180,15 +542,27 @@ impl SubstitutionVisitor {
             substitutions,
             imports: vec![],
             visited: 0,
+            current_name: None,
+            runtime_fallback,
+            directive: directive.to_owned(),
+            insert_use_client,
         }
     }
 
-    /// Substitute the function body with the codegen'd one, matching using
-    /// the span and prompt. (Not perfect, but good enough.)
-    fn transform_fn_body(self: &mut Self, func: &mut Function, span: Span) {
-        let Some(body) = &func.body else {
+    /// Substitute a function's body with the codegen'd one, matching using
+    /// the function name and prompt. (Not perfect, but good enough.)
+    fn transform_fn_body(&mut self, func: &mut Function, name: &str, span: Span) {
+        let Some(body) = &mut func.body else {
             return;
         };
+        self.transform_block_stmt(body, name, span);
+    }
+
+    /// Substitute a `use prompt:`-prefixed block with the codegen'd one,
+    /// matching using the function name and prompt. Shared by `FnDecl`,
+    /// `FnExpr`, `ArrowExpr` bodies, and class `render` methods. `span`
+    /// locates the enclosing function/directive for compile-time diagnostics.
+    fn transform_block_stmt(&mut self, body: &mut BlockStmt, name: &str, span: Span) {
         if body.stmts.is_empty() {
             return;
         };
@@ -206,10 +580,10 @@ impl SubstitutionVisitor {
         let prompt = prologue
             .iter()
             .filter_map(|s| {
-                if !s.starts_with("use prompt:") {
+                if !s.starts_with(self.directive.as_str()) {
                     return None;
                 };
-                let prompt = (&s[11..]).trim().to_owned();
+                let prompt = (&s[self.directive.len()..]).trim().to_owned();
                 if prompt.is_empty() {
                     return Some(Err(1));
                 }
@@ -221,23 +595,27 @@ impl SubstitutionVisitor {
             return;
         };
         let Ok(prompt) = prompt else {
-            func.body = make_prompt_error_body("ðŸ¤– Incomplete prompt!");
+            emit_prompt_diagnostic(
+                span,
+                &format!(
+                    "ðŸ¤– Incomplete prompt: write some text after `{}`",
+                    self.directive
+                ),
+            );
             return;
         };
 
         let visit_index = self.visited;
         self.visited += 1;
 
-        let subst = match self.substitutions.get(&span.lo.0.to_string()) {
-            Some(m) => match m.get(&span.hi.0.to_string()) {
-                Some(m) => m.get(&prompt),
-                None => None,
-            },
-            None => None,
-        };
+        let key = hash_substitution_key(name, &prompt);
+        let subst = self.substitutions.get(&key);
 
         let Some(subst) = subst else {
-            println!("âŒ› Waiting for component generation...");
+            emit_prompt_warning(span, "âŒ› Waiting for component generation...");
+            if self.runtime_fallback {
+                *body = make_prompt_error_body("âŒ› Waiting for component generation...");
+            }
             return;
         };
 
@@ -250,18 +628,25 @@ impl SubstitutionVisitor {
                     ident_map = new_ident_map;
                 }
                 Err(e) => {
-                    func.body = make_prompt_error_body(&format!("uh oh: {:?}", e));
+                    emit_prompt_diagnostic(
+                        span,
+                        &format!("ðŸ¤– Failed to parse generated imports: {:?}", e),
+                    );
                     return;
                 }
             }
         };
 
         match make_block_stmt_from_source(&subst.code, ident_map) {
-            Ok(body) => func.body = Some(body),
+            Ok(new_body) => *body = new_body,
             Err(e) => {
-                func.body =
-                    make_prompt_error_body("ðŸ¤– Guess ChatGPT isn't great at writing code...");
-                println!("Error: {:?}\n", e);
+                emit_prompt_diagnostic(
+                    span,
+                    &format!(
+                        "ðŸ¤– Guess ChatGPT isn't great at writing code... ({:?})",
+                        e
+                    ),
+                );
             }
         };
     }
@@ -271,21 +656,71 @@ impl VisitMut for SubstitutionVisitor {
     fn visit_mut_fn_decl(&mut self, node: &mut swc_core::ecma::ast::FnDecl) {
         node.visit_mut_children_with(self);
 
+        let name = node.ident.sym.to_string();
         let span = node.span();
-        self.transform_fn_body(&mut node.function, span);
+        self.transform_fn_body(&mut node.function, &name, span);
     }
 
     fn visit_mut_fn_expr(&mut self, node: &mut swc_core::ecma::ast::FnExpr) {
         node.visit_mut_children_with(self);
 
+        let name = node
+            .ident
+            .as_ref()
+            .map(|ident| ident.sym.to_string())
+            .unwrap_or_else(|| self.current_name.clone().unwrap_or_default());
+        let span = node.span();
+        self.transform_fn_body(&mut node.function, &name, span);
+    }
+
+    fn visit_mut_arrow_expr(&mut self, node: &mut ArrowExpr) {
+        node.visit_mut_children_with(self);
+
         let span = node.span();
-        self.transform_fn_body(&mut node.function, span);
+        if let BlockStmtOrExpr::BlockStmt(block) = &mut *node.body {
+            let name = self.current_name.clone().unwrap_or_default();
+            self.transform_block_stmt(block, &name, span);
+        }
+    }
+
+    fn visit_mut_var_declarator(&mut self, node: &mut VarDeclarator) {
+        let prev_name = self.current_name.take();
+        self.current_name = node.name.as_ident().map(|ident| ident.id.sym.to_string());
+        node.visit_mut_children_with(self);
+        self.current_name = prev_name;
+    }
+
+    fn visit_mut_class_decl(&mut self, node: &mut ClassDecl) {
+        let prev_name = self.current_name.take();
+        self.current_name = Some(node.ident.sym.to_string());
+        node.visit_mut_children_with(self);
+        self.current_name = prev_name;
+    }
+
+    fn visit_mut_class_method(&mut self, node: &mut ClassMethod) {
+        node.visit_mut_children_with(self);
+
+        if matches!(&node.key, PropName::Ident(ident) if ident.sym == *"render") {
+            let name = self.current_name.clone().unwrap_or_default();
+            let span = node.span();
+            self.transform_fn_body(&mut node.function, &name, span);
+        }
+    }
+
+    fn visit_mut_method_prop(&mut self, node: &mut MethodProp) {
+        node.visit_mut_children_with(self);
+
+        if matches!(&node.key, PropName::Ident(ident) if ident.sym == *"render") {
+            let name = self.current_name.clone().unwrap_or_default();
+            let span = node.span();
+            self.transform_fn_body(&mut node.function, &name, span);
+        }
     }
 
     fn visit_mut_module(&mut self, node: &mut Module) {
         node.visit_mut_children_with(self);
 
-        if self.visited > 0 {
+        if self.visited > 0 && self.insert_use_client {
             // ensure "use client"
             let has_client_directive = node
                 .body
@@ -313,7 +748,12 @@ impl VisitMut for SubstitutionVisitor {
             }
         }
 
-        node.body.extend(self.imports.clone());
+        let imports = std::mem::take(&mut self.imports);
+        let (merged_imports, rename) = merge_imports(imports);
+        if !rename.is_empty() {
+            RenameIdentVisitor::new(rename).visit_mut_module(node);
+        }
+        node.body.extend(merged_imports);
     }
 }
 
@@ -365,7 +805,25 @@ impl VisitMut for FixImportsVisitor {
 }
 
 #[plugin_transform]
-pub fn process_transform(program: Program, _metadata: TransformPluginProgramMetadata) -> Program {
-    let program = program.fold_with(&mut as_folder(SubstitutionVisitor::new(PROMPTS_FILE)));
-    program.fold_with(&mut as_folder(FixImportsVisitor::new()))
+pub fn process_transform(program: Program, metadata: TransformPluginProgramMetadata) -> Program {
+    let config: PluginConfig = metadata
+        .get_transform_plugin_config()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default();
+
+    let prompts_file = config.prompts_file.as_deref().unwrap_or(PROMPTS_FILE);
+    let directive = config.directive.as_deref().unwrap_or(DEFAULT_DIRECTIVE);
+
+    let program = program.fold_with(&mut as_folder(SubstitutionVisitor::new(
+        prompts_file,
+        directive,
+        !config.disable_use_client,
+        config.runtime_fallback,
+    )));
+
+    if config.disable_react_import {
+        program
+    } else {
+        program.fold_with(&mut as_folder(FixImportsVisitor::new()))
+    }
 }